@@ -0,0 +1,41 @@
+#![no_main]
+
+use bootstrap::messages::BootstrapMessage;
+use libfuzzer_sys::fuzz_target;
+use models::{DeserializeCompact, SerializationContext, SerializeCompact};
+
+/// Same shape used in the crate's own round-trip test, with generous ceilings
+/// so the fuzzer is exercising the decoder's bounds checks, not the context limits.
+fn fuzz_context() -> SerializationContext {
+    SerializationContext {
+        max_block_size: 1024 * 1024,
+        max_block_operations: 1024,
+        parent_count: 2,
+        max_peer_list_length: 128,
+        max_message_size: 3 * 1024 * 1024,
+        max_bootstrap_blocks: 100,
+        max_bootstrap_cliques: 100,
+        max_bootstrap_deps: 100,
+        max_bootstrap_children: 100,
+        max_ask_blocks_per_message: 10,
+        max_operations_per_message: 1024,
+        max_bootstrap_message_size: 100_000_000,
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let context = fuzz_context();
+
+    // decoding arbitrary bytes must never panic, only ever return an error
+    let decoded = match BootstrapMessage::from_bytes_compact(data, &context) {
+        Ok((message, cursor)) => (message, cursor),
+        Err(_) => return,
+    };
+
+    // anything that does decode must re-serialize to exactly the bytes it consumed
+    let (message, cursor) = decoded;
+    let reencoded = message
+        .to_bytes_compact(&context)
+        .expect("a successfully decoded message must always re-encode");
+    assert_eq!(&reencoded[..], &data[..cursor]);
+});
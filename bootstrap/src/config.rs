@@ -0,0 +1,88 @@
+use crypto::signature::PublicKey;
+use models::ModelsError;
+use serde::{Deserialize, Serialize};
+
+/// Lowest bootstrap protocol version this node can speak.
+pub const BOOTSTRAP_VERSION_MIN: u32 = 1;
+
+/// Highest bootstrap protocol version this node can speak.
+///
+/// Version 2 is the lowest version that accepts [`crate::messages::BootstrapAuth::Threshold`];
+/// see [`BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION`].
+pub const BOOTSTRAP_VERSION_MAX: u32 = 2;
+
+/// Lowest negotiated protocol version under which threshold authentication is accepted.
+/// A peer negotiated down to version 1 only ever produces/accepts `BootstrapAuth::Single`.
+pub const BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION: u32 = 2;
+
+/// Bootstrap-wide configuration.
+///
+/// Besides the existing single-key signing path, a deployment can enable
+/// threshold (t-of-n) authentication: `n` bootstrap operators each hold a
+/// share of a group private key, and a joining node only needs to trust the
+/// fixed `group_public_key` below, not any individual operator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootstrapConfig {
+    /// Public key verified against for `BootstrapAuth::Single`-authenticated messages.
+    pub single_signer_public_key: PublicKey,
+    /// Group public key used to verify threshold-signed bootstrap message chains.
+    ///
+    /// This is the only group key ever trusted for verification: a
+    /// `BootstrapAuth::Threshold` message also carries a `group_public_key` on
+    /// the wire, but that copy comes from the (possibly hostile) peer and must
+    /// never be used in place of this configured one.
+    pub group_public_key: PublicKey,
+    /// Minimum number of bootstrap operators that must jointly sign a message
+    /// chain for the resulting aggregate signature to be considered valid.
+    ///
+    /// This is enforced off-chain, at signing time: the t-of-n aggregation
+    /// scheme only ever produces `aggregate_signature` once `threshold`
+    /// operators have cooperated, so a single `verify_signature` against
+    /// `group_public_key` is sufficient proof the threshold was met.
+    /// `threshold` and `total_signers` are not consulted again during
+    /// verification; they exist so `BootstrapConfig::new` can catch a
+    /// misconfigured deployment (e.g. `threshold` no subset of the group
+    /// could ever reach) before it ships a config nobody can satisfy.
+    pub threshold: u32,
+    /// Total number of bootstrap operators configured for the group.
+    pub total_signers: u32,
+}
+
+impl BootstrapConfig {
+    /// Builds a `BootstrapConfig`, rejecting a `threshold` that no subset of
+    /// `total_signers` operators could ever reach.
+    pub fn new(
+        single_signer_public_key: PublicKey,
+        group_public_key: PublicKey,
+        threshold: u32,
+        total_signers: u32,
+    ) -> Result<Self, ModelsError> {
+        if threshold == 0 || threshold > total_signers {
+            return Err(ModelsError::DeserializeError(format!(
+                "threshold {} must be between 1 and total_signers {}",
+                threshold, total_signers
+            )));
+        }
+        Ok(BootstrapConfig {
+            single_signer_public_key,
+            group_public_key,
+            threshold,
+            total_signers,
+        })
+    }
+}
+
+/// Picks the protocol version to use for a bootstrap session, given the
+/// range of versions the client advertised in `BootstrapInitiation`.
+///
+/// The server always picks the highest version both sides support, so the
+/// wire format only ever needs to regress to an older peer's ceiling.
+pub fn negotiate_version(client_min: u32, client_max: u32) -> Option<u32> {
+    let min = client_min.max(BOOTSTRAP_VERSION_MIN);
+    let max = client_max.min(BOOTSTRAP_VERSION_MAX);
+    if min > max {
+        None
+    } else {
+        Some(max)
+    }
+}
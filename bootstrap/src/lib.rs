@@ -0,0 +1,11 @@
+// Note on scope: an earlier revision of this crate explored a differential-varint
+// encoding for sorted integer lists (block-id/period style fields), intended as an
+// opt-in path for `BootstrapPeers`/`BootsrapableGraph` list fields gated on the
+// negotiated protocol version. That codec was removed (not merely unwired) because
+// those list fields are serialized by `communication`/`consensus`, crates outside
+// this tree, so the encoding could not be threaded into their codecs here without
+// fabricating those crates. Revisit only alongside access to that code.
+
+pub mod config;
+pub mod messages;
+pub mod transfer;
@@ -1,6 +1,8 @@
+use crate::config::{BootstrapConfig, BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION};
 use communication::network::BootstrapPeers;
 use consensus::BootsrapableGraph;
-use crypto::signature::{Signature, SIGNATURE_SIZE_BYTES};
+use crypto::hash::Hash;
+use crypto::signature::{PublicKey, Signature, PUBLIC_KEY_SIZE_BYTES, SIGNATURE_SIZE_BYTES};
 use models::{
     array_from_slice, DeserializeCompact, DeserializeVarInt, ModelsError, SerializationContext,
     SerializeCompact, SerializeVarInt,
@@ -12,6 +14,141 @@ use time::UTime;
 
 pub const BOOTSTRAP_RANDOMNES_SIZE_BYTES: usize = 32;
 
+/// Authentication carried alongside a `BootstrapMessage`.
+///
+/// `Single` is the historical single-key scheme: one bootstrap node signs
+/// with its own private key. `Threshold` lets a deployment of `n` bootstrap
+/// operators jointly produce one aggregate signature that verifies against a
+/// fixed group public key as soon as at least `t` of them signed, so no
+/// single compromised operator can forge a message chain. The scheme is
+/// tagged on the wire so older, single-key-only peers keep decoding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BootstrapAuth {
+    /// Signature from a single bootstrap node's private key.
+    Single(Signature),
+    /// Aggregate signature valid against `group_public_key` once at least
+    /// the configured threshold of operators signed.
+    Threshold {
+        /// Aggregate signature produced by the signing operators.
+        aggregate_signature: Signature,
+        /// Group public key the aggregate signature verifies against.
+        group_public_key: PublicKey,
+    },
+}
+
+#[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+enum AuthSchemeId {
+    Single = 0u8,
+    Threshold = 1,
+}
+
+impl SerializeCompact for BootstrapAuth {
+    fn to_bytes_compact(&self, _context: &SerializationContext) -> Result<Vec<u8>, ModelsError> {
+        let mut res: Vec<u8> = Vec::new();
+        match self {
+            BootstrapAuth::Single(signature) => {
+                res.push(u8::from(AuthSchemeId::Single));
+                res.extend(&signature.to_bytes());
+            }
+            BootstrapAuth::Threshold {
+                aggregate_signature,
+                group_public_key,
+            } => {
+                res.push(u8::from(AuthSchemeId::Threshold));
+                res.extend(&aggregate_signature.to_bytes());
+                res.extend(&group_public_key.to_bytes());
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl DeserializeCompact for BootstrapAuth {
+    fn from_bytes_compact(
+        buffer: &[u8],
+        _context: &SerializationContext,
+    ) -> Result<(Self, usize), ModelsError> {
+        let mut cursor = 0usize;
+
+        check_remaining(buffer, cursor, 1)?;
+        let scheme_id: AuthSchemeId = buffer[cursor]
+            .try_into()
+            .map_err(|_| ModelsError::DeserializeError("invalid bootstrap auth scheme ID".into()))?;
+        cursor += 1;
+
+        let res = match scheme_id {
+            AuthSchemeId::Single => {
+                check_remaining(buffer, cursor, SIGNATURE_SIZE_BYTES)?;
+                let signature = Signature::from_bytes(&array_from_slice(&buffer[cursor..])?)?;
+                cursor += SIGNATURE_SIZE_BYTES;
+                BootstrapAuth::Single(signature)
+            }
+            AuthSchemeId::Threshold => {
+                check_remaining(buffer, cursor, SIGNATURE_SIZE_BYTES)?;
+                let aggregate_signature =
+                    Signature::from_bytes(&array_from_slice(&buffer[cursor..])?)?;
+                cursor += SIGNATURE_SIZE_BYTES;
+                check_remaining(buffer, cursor, PUBLIC_KEY_SIZE_BYTES)?;
+                let group_public_key =
+                    PublicKey::from_bytes(&array_from_slice(&buffer[cursor..])?)?;
+                cursor += PUBLIC_KEY_SIZE_BYTES;
+                BootstrapAuth::Threshold {
+                    aggregate_signature,
+                    group_public_key,
+                }
+            }
+        };
+        Ok((res, cursor))
+    }
+}
+
+/// Verifies a `BootstrapAuth` against `hash`, the hash of the message it authenticates.
+///
+/// `Single` verification checks the signature against
+/// `config.single_signer_public_key`. `Threshold` verification always checks
+/// `aggregate_signature` against `config.group_public_key`, the locally
+/// configured trust anchor: the `group_public_key` carried inside
+/// `BootstrapAuth::Threshold` itself comes from the peer and is never used to
+/// decide trust, only decoded so the wire format stays self-describing.
+pub fn verify_bootstrap_auth(
+    negotiated_version: u32,
+    auth: &BootstrapAuth,
+    hash: &Hash,
+    config: &BootstrapConfig,
+) -> Result<(), ModelsError> {
+    match auth {
+        BootstrapAuth::Single(signature) => config
+            .single_signer_public_key
+            .verify_signature(hash, signature)
+            .map_err(|_| {
+                ModelsError::DeserializeError(
+                    "signature does not verify against the configured single-signer public key"
+                        .into(),
+                )
+            }),
+        BootstrapAuth::Threshold {
+            aggregate_signature,
+            ..
+        } => {
+            if negotiated_version < BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION {
+                return Err(ModelsError::DeserializeError(format!(
+                    "threshold authentication requires negotiated protocol version >= {}, got {}",
+                    BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION, negotiated_version
+                )));
+            }
+            config
+                .group_public_key
+                .verify_signature(hash, aggregate_signature)
+                .map_err(|_| {
+                    ModelsError::DeserializeError(
+                        "threshold aggregate signature does not verify against the configured group public key".into(),
+                    )
+                })
+        }
+    }
+}
+
 /// Messages used during bootstrap
 #[derive(Debug, Serialize, Deserialize)]
 pub enum BootstrapMessage {
@@ -19,27 +156,58 @@ pub enum BootstrapMessage {
     BootstrapInitiation {
         /// Random data we expect the bootstrap node to sign with its private_key.
         random_bytes: [u8; BOOTSTRAP_RANDOMNES_SIZE_BYTES],
+        /// Lowest bootstrap protocol version the client can speak.
+        version_min: u32,
+        /// Highest bootstrap protocol version the client can speak.
+        version_max: u32,
+    },
+    /// Echoes back the protocol version the server picked from the client's
+    /// advertised `[version_min, version_max]` range.
+    BootstrapVersion {
+        /// Version selected for the rest of the bootstrap session.
+        version: u32,
     },
     /// Sync clocks,
     BootstrapTime {
         /// The curren time on the bootstrap server.
         server_time: UTime,
-        /// Signature of [BootstrapInitiation.random_bytes + server_time].
-        signature: Signature,
+        /// Authentication of [BootstrapInitiation.random_bytes + server_time].
+        auth: BootstrapAuth,
     },
     /// Sync clocks,
     BootstrapPeers {
         /// Server peers
         peers: BootstrapPeers,
-        /// Signature of [BootstrapTime.signature + peers]
-        signature: Signature,
+        /// Authentication of [BootstrapTime.auth + peers]
+        auth: BootstrapAuth,
     },
     /// Global consensus state
     ConsensusState {
         /// Content
         graph: BootsrapableGraph,
-        /// Signature of [BootstrapPeers.signature + peers]
-        signature: Signature,
+        /// Authentication of [BootstrapPeers.auth + peers]
+        auth: BootstrapAuth,
+    },
+    /// Asks the server for the next slice of the consensus state, resuming
+    /// after the last part the client has received and verified.
+    ConsensusStateRequest {
+        /// Byte offset into the server's compact-encoded `BootsrapableGraph`
+        /// the client has already verified; 0 to start a fresh transfer.
+        cursor: u64,
+    },
+    /// One bounded slice of a `BootsrapableGraph` transfer, answering a
+    /// `ConsensusStateRequest`. The client reassembles the full graph only
+    /// once every part from 0 to `total_parts - 1` has verified.
+    ConsensusStatePart {
+        /// Index of this part in the overall transfer, starting at 0.
+        part_index: u32,
+        /// Total number of parts making up this transfer.
+        total_parts: u32,
+        /// Slice of the compact-encoded `BootsrapableGraph` covered by this part.
+        data: Vec<u8>,
+        /// Authentication of this part's `data` alone; it does not cover any
+        /// previous part or its authentication.
+        auth: BootstrapAuth,
     },
 }
 
@@ -50,44 +218,138 @@ enum MessageTypeId {
     BootstrapTime = 1,
     Peers = 2,
     ConsensusState = 3,
+    BootstrapVersion = 4,
+    ConsensusStateRequest = 5,
+    ConsensusStatePart = 6,
 }
 
 impl SerializeCompact for BootstrapMessage {
     fn to_bytes_compact(&self, context: &SerializationContext) -> Result<Vec<u8>, ModelsError> {
         let mut res: Vec<u8> = Vec::new();
         match self {
-            BootstrapMessage::BootstrapInitiation { random_bytes } => {
+            BootstrapMessage::BootstrapInitiation {
+                random_bytes,
+                version_min,
+                version_max,
+            } => {
                 res.extend(u32::from(MessageTypeId::BootstrapInitiation).to_varint_bytes());
                 res.extend(random_bytes);
+                res.extend(version_min.to_varint_bytes());
+                res.extend(version_max.to_varint_bytes());
             }
-            BootstrapMessage::BootstrapTime {
-                server_time,
-                signature,
-            } => {
+            BootstrapMessage::BootstrapVersion { version } => {
+                res.extend(u32::from(MessageTypeId::BootstrapVersion).to_varint_bytes());
+                res.extend(version.to_varint_bytes());
+            }
+            BootstrapMessage::BootstrapTime { server_time, auth } => {
                 res.extend(u32::from(MessageTypeId::BootstrapTime).to_varint_bytes());
-                res.extend(&signature.to_bytes());
+                res.extend(&auth.to_bytes_compact(context)?);
                 res.extend(server_time.to_bytes_compact(context)?);
             }
-            BootstrapMessage::BootstrapPeers { peers, signature } => {
+            BootstrapMessage::BootstrapPeers { peers, auth } => {
                 res.extend(u32::from(MessageTypeId::Peers).to_varint_bytes());
-                res.extend(&signature.to_bytes());
+                res.extend(&auth.to_bytes_compact(context)?);
                 res.extend(&peers.to_bytes_compact(&context)?);
             }
-            BootstrapMessage::ConsensusState { graph, signature } => {
+            BootstrapMessage::ConsensusState { graph, auth } => {
                 res.extend(u32::from(MessageTypeId::ConsensusState).to_varint_bytes());
-                res.extend(&signature.to_bytes());
+                res.extend(&auth.to_bytes_compact(context)?);
                 res.extend(&graph.to_bytes_compact(&context)?);
             }
+            BootstrapMessage::ConsensusStateRequest { cursor } => {
+                res.extend(u32::from(MessageTypeId::ConsensusStateRequest).to_varint_bytes());
+                res.extend(cursor.to_varint_bytes());
+            }
+            BootstrapMessage::ConsensusStatePart {
+                part_index,
+                total_parts,
+                data,
+                auth,
+            } => {
+                res.extend(u32::from(MessageTypeId::ConsensusStatePart).to_varint_bytes());
+                res.extend(part_index.to_varint_bytes());
+                res.extend(total_parts.to_varint_bytes());
+                res.extend((data.len() as u64).to_varint_bytes());
+                res.extend(data);
+                res.extend(&auth.to_bytes_compact(context)?);
+            }
         }
         Ok(res)
     }
 }
 
+/// Checks that `buffer` has at least `needed` bytes left from `cursor`,
+/// so callers can slice or call `array_from_slice` without risking a panic
+/// on truncated or adversarial input.
+fn check_remaining(buffer: &[u8], cursor: usize, needed: usize) -> Result<(), ModelsError> {
+    if buffer.len().saturating_sub(cursor) < needed {
+        return Err(ModelsError::DeserializeError(
+            "unexpected end of buffer while deserializing BootstrapMessage".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the leading element-count varint a compact-encoded `Vec` starts
+/// with, without advancing past it, so callers can reject an oversized count
+/// against a `SerializationContext` ceiling before the nested decoder
+/// `Vec::with_capacity`s for it.
+fn peek_count(buffer: &[u8]) -> Result<u32, ModelsError> {
+    let (count, _delta) = u32::from_varint_bytes(buffer)?;
+    Ok(count)
+}
+
+fn check_count(count: u32, max: u32, what: &str) -> Result<(), ModelsError> {
+    if count > max {
+        return Err(ModelsError::DeserializeError(format!(
+            "{} count {} exceeds the configured maximum {}",
+            what, count, max
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects a decoded `BootsrapableGraph` whose nested collections exceed the
+/// configured ceilings.
+///
+/// This runs *after* `BootsrapableGraph::from_bytes_compact` has already
+/// allocated, since that decoder lives in the `consensus` crate, outside this
+/// tree, and its internal field layout (in particular `active_blocks`'
+/// per-block element type) isn't something this crate can parse or bound
+/// ahead of time without duplicating consensus's own decoder. It still
+/// catches an oversized `gi_head`/`max_cliques` before the message is
+/// accepted and turned into further work. `max_bootstrap_children` has no
+/// corresponding check here: it bounds a per-block children count nested
+/// inside `active_blocks`' element type, which is opaque from this crate.
+fn check_graph_ceilings(
+    graph: &BootsrapableGraph,
+    context: &SerializationContext,
+) -> Result<(), ModelsError> {
+    check_count(
+        graph.max_cliques.len() as u32,
+        context.max_bootstrap_cliques,
+        "max_cliques",
+    )?;
+    let gi_head_deps: usize = graph.gi_head.values().map(|deps| deps.len()).sum();
+    check_count(
+        gi_head_deps as u32,
+        context.max_bootstrap_deps,
+        "gi_head dependencies",
+    )?;
+    Ok(())
+}
+
 impl DeserializeCompact for BootstrapMessage {
     fn from_bytes_compact(
         buffer: &[u8],
         context: &SerializationContext,
     ) -> Result<(Self, usize), ModelsError> {
+        if buffer.len() > context.max_bootstrap_message_size as usize {
+            return Err(ModelsError::DeserializeError(
+                "BootstrapMessage exceeds max_bootstrap_message_size".into(),
+            ));
+        }
+
         let mut cursor = 0usize;
 
         let (type_id_raw, delta) = u32::from_varint_bytes(&buffer[cursor..])?;
@@ -100,39 +362,112 @@ impl DeserializeCompact for BootstrapMessage {
         let res = match type_id {
             MessageTypeId::BootstrapInitiation => {
                 // random bytes
+                check_remaining(buffer, cursor, BOOTSTRAP_RANDOMNES_SIZE_BYTES)?;
                 let random_bytes: [u8; BOOTSTRAP_RANDOMNES_SIZE_BYTES] =
                     array_from_slice(&buffer[cursor..])?;
                 cursor += BOOTSTRAP_RANDOMNES_SIZE_BYTES;
+                let (version_min, delta) = u32::from_varint_bytes(&buffer[cursor..])?;
+                cursor += delta;
+                let (version_max, delta) = u32::from_varint_bytes(&buffer[cursor..])?;
+                cursor += delta;
+                // `SerializationContext` is defined in the `models` crate, outside this
+                // tree, so it can't gain a version field here; negotiation instead lives
+                // on the bootstrap crate's own config and is consulted right away, as
+                // soon as the client's advertised range is known.
+                if crate::config::negotiate_version(version_min, version_max).is_none() {
+                    return Err(ModelsError::DeserializeError(format!(
+                        "no protocol version in [{}, {}] is supported by this node (supports [{}, {}])",
+                        version_min,
+                        version_max,
+                        crate::config::BOOTSTRAP_VERSION_MIN,
+                        crate::config::BOOTSTRAP_VERSION_MAX
+                    )));
+                }
                 // return message
-                BootstrapMessage::BootstrapInitiation { random_bytes }
+                BootstrapMessage::BootstrapInitiation {
+                    random_bytes,
+                    version_min,
+                    version_max,
+                }
+            }
+            MessageTypeId::BootstrapVersion => {
+                let (version, delta) = u32::from_varint_bytes(&buffer[cursor..])?;
+                cursor += delta;
+                BootstrapMessage::BootstrapVersion { version }
             }
             MessageTypeId::BootstrapTime => {
-                let signature = Signature::from_bytes(&array_from_slice(&buffer[cursor..])?)?;
-                cursor += SIGNATURE_SIZE_BYTES;
+                let (auth, delta) = BootstrapAuth::from_bytes_compact(&buffer[cursor..], context)?;
+                cursor += delta;
                 let (server_time, delta) = UTime::from_bytes_compact(&buffer[cursor..], context)?;
                 cursor += delta;
-                BootstrapMessage::BootstrapTime {
-                    server_time,
-                    signature,
-                }
+                BootstrapMessage::BootstrapTime { server_time, auth }
             }
             MessageTypeId::Peers => {
-                let signature = Signature::from_bytes(&array_from_slice(&buffer[cursor..])?)?;
-                cursor += SIGNATURE_SIZE_BYTES;
+                let (auth, delta) = BootstrapAuth::from_bytes_compact(&buffer[cursor..], context)?;
+                cursor += delta;
+                // reject an oversized peer count before BootstrapPeers::from_bytes_compact
+                // allocates a Vec for it
+                check_count(
+                    peek_count(&buffer[cursor..])?,
+                    context.max_peer_list_length,
+                    "peer list",
+                )?;
                 let (peers, delta) =
                     BootstrapPeers::from_bytes_compact(&buffer[cursor..], &context)?;
                 cursor += delta;
 
-                BootstrapMessage::BootstrapPeers { signature, peers }
+                BootstrapMessage::BootstrapPeers { auth, peers }
             }
             MessageTypeId::ConsensusState => {
-                let signature = Signature::from_bytes(&array_from_slice(&buffer[cursor..])?)?;
-                cursor += SIGNATURE_SIZE_BYTES;
+                let (auth, delta) = BootstrapAuth::from_bytes_compact(&buffer[cursor..], context)?;
+                cursor += delta;
+                // BootsrapableGraph serializes active_blocks first; reject an oversized
+                // count before it allocates a Vec for it
+                check_count(
+                    peek_count(&buffer[cursor..])?,
+                    context.max_bootstrap_blocks,
+                    "active block",
+                )?;
                 let (graph, delta) =
                     BootsrapableGraph::from_bytes_compact(&buffer[cursor..], &context)?;
                 cursor += delta;
+                check_graph_ceilings(&graph, context)?;
 
-                BootstrapMessage::ConsensusState { signature, graph }
+                BootstrapMessage::ConsensusState { auth, graph }
+            }
+            MessageTypeId::ConsensusStateRequest => {
+                let (cursor_val, delta) = u64::from_varint_bytes(&buffer[cursor..])?;
+                cursor += delta;
+                BootstrapMessage::ConsensusStateRequest { cursor: cursor_val }
+            }
+            MessageTypeId::ConsensusStatePart => {
+                let (part_index, delta) = u32::from_varint_bytes(&buffer[cursor..])?;
+                cursor += delta;
+                let (total_parts, delta) = u32::from_varint_bytes(&buffer[cursor..])?;
+                cursor += delta;
+                if part_index >= total_parts {
+                    return Err(ModelsError::DeserializeError(
+                        "ConsensusStatePart part_index must be lower than total_parts".into(),
+                    ));
+                }
+                let (data_len, delta) = u64::from_varint_bytes(&buffer[cursor..])?;
+                cursor += delta;
+                if data_len as usize > context.max_bootstrap_message_size as usize {
+                    return Err(ModelsError::DeserializeError(
+                        "ConsensusStatePart data exceeds max_bootstrap_message_size".into(),
+                    ));
+                }
+                check_remaining(buffer, cursor, data_len as usize)?;
+                let data = buffer[cursor..cursor + data_len as usize].to_vec();
+                cursor += data_len as usize;
+                let (auth, delta) = BootstrapAuth::from_bytes_compact(&buffer[cursor..], context)?;
+                cursor += delta;
+                BootstrapMessage::ConsensusStatePart {
+                    part_index,
+                    total_parts,
+                    data,
+                    auth,
+                }
             }
         };
         Ok((res, cursor))
@@ -145,10 +480,10 @@ mod tests {
     use models::BlockId;
     use rand::{rngs::StdRng, RngCore, SeedableRng};
 
-    #[test]
-    fn test_message_serialize_compact() {
-        //test with 2 thread
-        let serialization_context = SerializationContext {
+    /// Shared fixture for tests that don't care about the exact limits,
+    /// so each test only spells out the field it actually wants to vary.
+    fn test_context() -> SerializationContext {
+        SerializationContext {
             max_block_size: 1024 * 1024,
             max_block_operations: 1024,
             parent_count: 2,
@@ -161,12 +496,20 @@ mod tests {
             max_ask_blocks_per_message: 10,
             max_operations_per_message: 1024,
             max_bootstrap_message_size: 100000000,
-        };
+        }
+    }
+
+    #[test]
+    fn test_message_serialize_compact() {
+        //test with 2 thread
+        let serialization_context = test_context();
 
         let mut base_random_bytes = [0u8; 32];
         StdRng::from_entropy().fill_bytes(&mut base_random_bytes);
         let message1 = BootstrapMessage::BootstrapInitiation {
             random_bytes: base_random_bytes,
+            version_min: 1,
+            version_max: 1,
         };
 
         let bytes = message1.to_bytes_compact(&serialization_context).unwrap();
@@ -174,8 +517,15 @@ mod tests {
             BootstrapMessage::from_bytes_compact(&bytes, &serialization_context).unwrap();
         assert_eq!(bytes.len(), cursor);
 
-        if let BootstrapMessage::BootstrapInitiation { random_bytes } = new_message1 {
+        if let BootstrapMessage::BootstrapInitiation {
+            random_bytes,
+            version_min,
+            version_max,
+        } = new_message1
+        {
             assert_eq!(base_random_bytes, random_bytes);
+            assert_eq!(1, version_min);
+            assert_eq!(1, version_max);
         } else {
             panic!("not the right message variant expected BootstrapInitiation");
         }
@@ -241,15 +591,15 @@ mod tests {
 
         let message2 = BootstrapMessage::ConsensusState {
             graph: base_graph,
-            signature: base_signature,
+            auth: BootstrapAuth::Single(base_signature),
         };
         let bytes = message2.to_bytes_compact(&serialization_context).unwrap();
         let (new_message2, cursor) =
             BootstrapMessage::from_bytes_compact(&bytes, &serialization_context).unwrap();
 
         assert_eq!(bytes.len(), cursor);
-        if let BootstrapMessage::ConsensusState { graph, signature } = new_message2 {
-            assert_eq!(base_signature, signature);
+        if let BootstrapMessage::ConsensusState { graph, auth } = new_message2 {
+            assert_eq!(BootstrapAuth::Single(base_signature), auth);
             assert_eq!(
                 BlockId::for_tests("parent11").unwrap(),
                 graph.best_parents[0]
@@ -262,4 +612,281 @@ mod tests {
             panic!("not the right message variant expected ConsensusState");
         }
     }
+
+    #[test]
+    fn test_threshold_auth_serialize_compact() {
+        let serialization_context = test_context();
+
+        let group_private_key = crypto::generate_random_private_key();
+        let group_public_key = group_private_key.get_public_key();
+        let aggregate_signature = group_private_key
+            .sign(&crypto::hash::Hash::hash(b"some bootstrap message chain"))
+            .unwrap();
+
+        let auth = BootstrapAuth::Threshold {
+            aggregate_signature,
+            group_public_key,
+        };
+        let bytes = auth.to_bytes_compact(&serialization_context).unwrap();
+        let (new_auth, cursor) =
+            BootstrapAuth::from_bytes_compact(&bytes, &serialization_context).unwrap();
+        assert_eq!(bytes.len(), cursor);
+        assert_eq!(auth, new_auth);
+    }
+
+    #[test]
+    fn test_verify_bootstrap_auth_threshold_checks_configured_key_not_wire_key() {
+        let hash = crypto::hash::Hash::hash(b"some bootstrap message chain");
+
+        let group_private_key = crypto::generate_random_private_key();
+        let group_public_key = group_private_key.get_public_key();
+        let aggregate_signature = group_private_key.sign(&hash).unwrap();
+
+        let single_signer_private_key = crypto::generate_random_private_key();
+        let single_signer_public_key = single_signer_private_key.get_public_key();
+
+        let config =
+            BootstrapConfig::new(single_signer_public_key, group_public_key, 2, 3).unwrap();
+
+        // an attacker swapping in their own key on the wire must not help them
+        let forged_private_key = crypto::generate_random_private_key();
+        let forged_public_key = forged_private_key.get_public_key();
+        let auth = BootstrapAuth::Threshold {
+            aggregate_signature: aggregate_signature.clone(),
+            group_public_key: forged_public_key,
+        };
+        assert!(verify_bootstrap_auth(BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION, &auth, &hash, &config)
+            .is_ok());
+
+        // a signature that doesn't actually verify against the configured key must be rejected
+        let other_private_key = crypto::generate_random_private_key();
+        let bad_signature = other_private_key.sign(&hash).unwrap();
+        let bad_auth = BootstrapAuth::Threshold {
+            aggregate_signature: bad_signature,
+            group_public_key,
+        };
+        assert!(
+            verify_bootstrap_auth(BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION, &bad_auth, &hash, &config)
+                .is_err()
+        );
+
+        // threshold auth negotiated below its minimum version is rejected outright
+        assert!(verify_bootstrap_auth(
+            BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION - 1,
+            &auth,
+            &hash,
+            &config
+        )
+        .is_err());
+
+        // single-key auth verifies against the configured single-signer key
+        let single_signature = single_signer_private_key.sign(&hash).unwrap();
+        let single_auth = BootstrapAuth::Single(single_signature);
+        assert!(verify_bootstrap_auth(1, &single_auth, &hash, &config).is_ok());
+
+        // ...and is rejected if it doesn't verify against that key
+        let forged_single_auth = BootstrapAuth::Single(aggregate_signature);
+        assert!(verify_bootstrap_auth(1, &forged_single_auth, &hash, &config).is_err());
+    }
+
+    #[test]
+    fn test_truncated_buffer_does_not_panic() {
+        let serialization_context = test_context();
+
+        let message = BootstrapMessage::BootstrapInitiation {
+            random_bytes: [0u8; BOOTSTRAP_RANDOMNES_SIZE_BYTES],
+            version_min: 1,
+            version_max: 1,
+        };
+        let bytes = message.to_bytes_compact(&serialization_context).unwrap();
+
+        // truncating the buffer at any point must yield a clean error, never a panic
+        for end in 0..bytes.len() {
+            assert!(BootstrapMessage::from_bytes_compact(&bytes[..end], &serialization_context)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_message_over_max_bootstrap_message_size_is_rejected() {
+        let serialization_context = SerializationContext {
+            max_bootstrap_message_size: 10,
+            ..test_context()
+        };
+
+        let message = BootstrapMessage::BootstrapInitiation {
+            random_bytes: [0u8; BOOTSTRAP_RANDOMNES_SIZE_BYTES],
+            version_min: 1,
+            version_max: 1,
+        };
+        let bytes = message.to_bytes_compact(&serialization_context).unwrap();
+        assert!(bytes.len() > serialization_context.max_bootstrap_message_size as usize);
+        assert!(BootstrapMessage::from_bytes_compact(&bytes, &serialization_context).is_err());
+    }
+
+    #[test]
+    fn test_bootstrap_version_serialize_compact() {
+        let serialization_context = test_context();
+
+        let message = BootstrapMessage::BootstrapVersion { version: 1 };
+        let bytes = message.to_bytes_compact(&serialization_context).unwrap();
+        let (new_message, cursor) =
+            BootstrapMessage::from_bytes_compact(&bytes, &serialization_context).unwrap();
+        assert_eq!(bytes.len(), cursor);
+        if let BootstrapMessage::BootstrapVersion { version } = new_message {
+            assert_eq!(1, version);
+        } else {
+            panic!("not the right message variant expected BootstrapVersion");
+        }
+    }
+
+    #[test]
+    fn test_negotiate_version() {
+        use crate::config::{negotiate_version, BOOTSTRAP_VERSION_MAX};
+
+        assert_eq!(negotiate_version(1, 1), Some(1));
+        assert_eq!(negotiate_version(1, BOOTSTRAP_VERSION_MAX), Some(BOOTSTRAP_VERSION_MAX));
+        assert_eq!(negotiate_version(BOOTSTRAP_VERSION_MAX + 1, BOOTSTRAP_VERSION_MAX + 5), None);
+    }
+
+    #[test]
+    fn test_consensus_state_chunked_transfer_serialize_compact() {
+        let serialization_context = test_context();
+
+        let request = BootstrapMessage::ConsensusStateRequest { cursor: 4096 };
+        let bytes = request.to_bytes_compact(&serialization_context).unwrap();
+        let (new_request, cursor) =
+            BootstrapMessage::from_bytes_compact(&bytes, &serialization_context).unwrap();
+        assert_eq!(bytes.len(), cursor);
+        if let BootstrapMessage::ConsensusStateRequest { cursor } = new_request {
+            assert_eq!(4096, cursor);
+        } else {
+            panic!("not the right message variant expected ConsensusStateRequest");
+        }
+
+        let base_signature = crypto::signature::Signature::from_bs58_check(
+                    "5f4E3opXPWc3A1gvRVV7DJufvabDfaLkT1GMterpJXqRZ5B7bxPe5LoNzGDQp9LkphQuChBN1R5yEvVJqanbjx7mgLEae"
+                ).unwrap();
+        let part = BootstrapMessage::ConsensusStatePart {
+            part_index: 2,
+            total_parts: 5,
+            data: vec![1, 2, 3, 4, 5],
+            auth: BootstrapAuth::Single(base_signature),
+        };
+        let bytes = part.to_bytes_compact(&serialization_context).unwrap();
+        let (new_part, cursor) =
+            BootstrapMessage::from_bytes_compact(&bytes, &serialization_context).unwrap();
+        assert_eq!(bytes.len(), cursor);
+        if let BootstrapMessage::ConsensusStatePart {
+            part_index,
+            total_parts,
+            data,
+            auth,
+        } = new_part
+        {
+            assert_eq!(2, part_index);
+            assert_eq!(5, total_parts);
+            assert_eq!(vec![1, 2, 3, 4, 5], data);
+            assert_eq!(BootstrapAuth::Single(base_signature), auth);
+        } else {
+            panic!("not the right message variant expected ConsensusStatePart");
+        }
+    }
+
+    #[test]
+    fn test_consensus_state_part_rejects_out_of_range_index() {
+        let serialization_context = test_context();
+
+        let base_signature = crypto::signature::Signature::from_bs58_check(
+                    "5f4E3opXPWc3A1gvRVV7DJufvabDfaLkT1GMterpJXqRZ5B7bxPe5LoNzGDQp9LkphQuChBN1R5yEvVJqanbjx7mgLEae"
+                ).unwrap();
+        let part = BootstrapMessage::ConsensusStatePart {
+            part_index: 5,
+            total_parts: 5,
+            data: vec![],
+            auth: BootstrapAuth::Single(base_signature),
+        };
+        let bytes = part.to_bytes_compact(&serialization_context).unwrap();
+        assert!(BootstrapMessage::from_bytes_compact(&bytes, &serialization_context).is_err());
+    }
+
+    #[test]
+    fn test_check_count_rejects_count_above_ceiling() {
+        assert!(check_count(5, 10, "test").is_ok());
+        assert!(check_count(10, 10, "test").is_ok());
+        assert!(check_count(11, 10, "test").is_err());
+    }
+
+    #[test]
+    fn test_peek_count_reads_leading_varint_without_advancing() {
+        let bytes = 42u32.to_varint_bytes();
+        assert_eq!(42, peek_count(&bytes).unwrap());
+    }
+
+    #[test]
+    fn test_check_graph_ceilings_rejects_oversized_max_cliques_and_gi_head() {
+        let context = SerializationContext {
+            max_bootstrap_cliques: 1,
+            max_bootstrap_deps: 1,
+            ..test_context()
+        };
+
+        let small_graph = BootsrapableGraph {
+            active_blocks: Vec::new(),
+            best_parents: vec![BlockId::for_tests("parent1").unwrap()],
+            latest_final_blocks_periods: vec![(BlockId::for_tests("lfinal1").unwrap(), 1)],
+            gi_head: vec![(
+                BlockId::for_tests("gi_head1").unwrap(),
+                vec![BlockId::for_tests("dep1").unwrap()].into_iter().collect(),
+            )]
+            .into_iter()
+            .collect(),
+            max_cliques: vec![vec![BlockId::for_tests("clique1").unwrap()]
+                .into_iter()
+                .collect()],
+        };
+        assert!(check_graph_ceilings(&small_graph, &context).is_ok());
+
+        let too_many_cliques = BootsrapableGraph {
+            active_blocks: Vec::new(),
+            best_parents: vec![BlockId::for_tests("parent1").unwrap()],
+            latest_final_blocks_periods: vec![(BlockId::for_tests("lfinal1").unwrap(), 1)],
+            gi_head: vec![(
+                BlockId::for_tests("gi_head1").unwrap(),
+                vec![BlockId::for_tests("dep1").unwrap()].into_iter().collect(),
+            )]
+            .into_iter()
+            .collect(),
+            max_cliques: vec![
+                vec![BlockId::for_tests("clique1").unwrap()]
+                    .into_iter()
+                    .collect(),
+                vec![BlockId::for_tests("clique2").unwrap()]
+                    .into_iter()
+                    .collect(),
+            ],
+        };
+        assert!(check_graph_ceilings(&too_many_cliques, &context).is_err());
+
+        let too_many_deps = BootsrapableGraph {
+            active_blocks: Vec::new(),
+            best_parents: vec![BlockId::for_tests("parent1").unwrap()],
+            latest_final_blocks_periods: vec![(BlockId::for_tests("lfinal1").unwrap(), 1)],
+            gi_head: vec![(
+                BlockId::for_tests("gi_head1").unwrap(),
+                vec![
+                    BlockId::for_tests("dep1").unwrap(),
+                    BlockId::for_tests("dep2").unwrap(),
+                ]
+                .into_iter()
+                .collect(),
+            )]
+            .into_iter()
+            .collect(),
+            max_cliques: vec![vec![BlockId::for_tests("clique1").unwrap()]
+                .into_iter()
+                .collect()],
+        };
+        assert!(check_graph_ceilings(&too_many_deps, &context).is_err());
+    }
 }
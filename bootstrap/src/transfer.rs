@@ -0,0 +1,267 @@
+use crate::config::BootstrapConfig;
+use crate::messages::{verify_bootstrap_auth, BootstrapMessage};
+use crypto::hash::Hash;
+use models::ModelsError;
+
+/// Client-side state for a chunked `ConsensusState` transfer.
+///
+/// The client drives the exchange by repeatedly sending a
+/// `ConsensusStateRequest { cursor }` and feeding each `ConsensusStatePart`
+/// reply to [`ConsensusStateTransfer::receive_part`]. Parts must arrive in
+/// order: [`ConsensusStateTransfer::receive_part`] only accepts the next
+/// contiguous `part_index`, so [`ConsensusStateTransfer::next_cursor`] — the
+/// total size of every part accepted so far — always lands exactly on a part
+/// boundary the server can resume from. A part is only kept if it verifies
+/// against the configured `BootstrapConfig` and agrees with every other part
+/// received so far on `total_parts`; the full compact-encoded graph is
+/// available through [`ConsensusStateTransfer::reassemble`] only once every
+/// part from `0` to `total_parts - 1` has been accepted.
+pub struct ConsensusStateTransfer {
+    negotiated_version: u32,
+    total_parts: Option<u32>,
+    parts: Vec<Vec<u8>>,
+}
+
+impl ConsensusStateTransfer {
+    /// Starts a fresh transfer for a session negotiated at `negotiated_version`.
+    pub fn new(negotiated_version: u32) -> Self {
+        ConsensusStateTransfer {
+            negotiated_version,
+            total_parts: None,
+            parts: Vec::new(),
+        }
+    }
+
+    /// Byte offset to resume from: the total size, in bytes, of every part
+    /// accepted so far. Sent as the `cursor` of the next `ConsensusStateRequest`.
+    ///
+    /// Since [`ConsensusStateTransfer::receive_part`] only ever accepts parts
+    /// in order, `self.parts` is always a gap-free prefix of the transfer, so
+    /// this sum is always a real resume point.
+    pub fn next_cursor(&self) -> u64 {
+        self.parts.iter().map(|data| data.len() as u64).sum()
+    }
+
+    /// Whether every part from `0` to `total_parts - 1` has been accepted.
+    pub fn is_complete(&self) -> bool {
+        match self.total_parts {
+            Some(total_parts) => total_parts > 0 && self.parts.len() == total_parts as usize,
+            None => false,
+        }
+    }
+
+    /// Verifies and records one `ConsensusStatePart` message.
+    ///
+    /// Rejects parts whose `total_parts` disagrees with a previously accepted
+    /// part, parts that aren't the next expected `part_index` (out-of-order
+    /// and duplicate parts alike), and parts whose `auth` doesn't verify
+    /// against `config`'s configured key.
+    pub fn receive_part(
+        &mut self,
+        message: &BootstrapMessage,
+        config: &BootstrapConfig,
+    ) -> Result<(), ModelsError> {
+        let (part_index, total_parts, data, auth) = match message {
+            BootstrapMessage::ConsensusStatePart {
+                part_index,
+                total_parts,
+                data,
+                auth,
+            } => (*part_index, *total_parts, data, auth),
+            _ => {
+                return Err(ModelsError::DeserializeError(
+                    "expected a ConsensusStatePart message".into(),
+                ))
+            }
+        };
+
+        if part_index >= total_parts {
+            return Err(ModelsError::DeserializeError(
+                "ConsensusStatePart part_index must be lower than total_parts".into(),
+            ));
+        }
+
+        if let Some(expected_total_parts) = self.total_parts {
+            if expected_total_parts != total_parts {
+                return Err(ModelsError::DeserializeError(format!(
+                    "ConsensusStatePart total_parts changed mid-transfer: expected {}, got {}",
+                    expected_total_parts, total_parts
+                )));
+            }
+        }
+
+        let expected_part_index = self.parts.len() as u32;
+        if part_index != expected_part_index {
+            return Err(ModelsError::DeserializeError(format!(
+                "ConsensusStatePart {} received out of order: expected {}",
+                part_index, expected_part_index
+            )));
+        }
+
+        verify_bootstrap_auth(self.negotiated_version, auth, &Hash::hash(data), config)?;
+
+        self.total_parts = Some(total_parts);
+        self.parts.push(data.clone());
+        Ok(())
+    }
+
+    /// Reassembles the full compact-encoded `BootsrapableGraph` once
+    /// [`ConsensusStateTransfer::is_complete`] holds, in part order.
+    pub fn reassemble(&self) -> Result<Vec<u8>, ModelsError> {
+        if !self.is_complete() {
+            return Err(ModelsError::DeserializeError(
+                "cannot reassemble an incomplete ConsensusState transfer".into(),
+            ));
+        }
+        Ok(self.parts.iter().flat_map(|data| data.iter().copied()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION;
+    use crate::messages::BootstrapAuth;
+
+    fn signed_part(
+        part_index: u32,
+        total_parts: u32,
+        data: Vec<u8>,
+        signing_key: &crypto::signature::PrivateKey,
+        group_public_key: crypto::signature::PublicKey,
+    ) -> BootstrapMessage {
+        let aggregate_signature = signing_key.sign(&Hash::hash(&data)).unwrap();
+        BootstrapMessage::ConsensusStatePart {
+            part_index,
+            total_parts,
+            data,
+            auth: BootstrapAuth::Threshold {
+                aggregate_signature,
+                group_public_key,
+            },
+        }
+    }
+
+    fn test_config(group_public_key: crypto::signature::PublicKey) -> BootstrapConfig {
+        let single_signer_public_key = crypto::generate_random_private_key().get_public_key();
+        BootstrapConfig::new(single_signer_public_key, group_public_key, 2, 3).unwrap()
+    }
+
+    #[test]
+    fn test_transfer_resumes_and_reassembles_in_order() {
+        let group_private_key = crypto::generate_random_private_key();
+        let group_public_key = group_private_key.get_public_key();
+        let config = test_config(group_public_key.clone());
+
+        let mut transfer = ConsensusStateTransfer::new(BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION);
+        assert_eq!(transfer.next_cursor(), 0);
+        assert!(!transfer.is_complete());
+
+        let part0 = signed_part(
+            0,
+            2,
+            vec![1, 2, 3],
+            &group_private_key,
+            group_public_key.clone(),
+        );
+        transfer.receive_part(&part0, &config).unwrap();
+        assert_eq!(transfer.next_cursor(), 3);
+        assert!(!transfer.is_complete());
+
+        // receiving the same part again must not corrupt the transfer
+        assert!(transfer.receive_part(&part0, &config).is_err());
+
+        let part1 = signed_part(1, 2, vec![4, 5], &group_private_key, group_public_key);
+        transfer.receive_part(&part1, &config).unwrap();
+        assert!(transfer.is_complete());
+        assert_eq!(transfer.reassemble().unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_transfer_rejects_part_not_verifying_against_configured_key() {
+        let group_private_key = crypto::generate_random_private_key();
+        let group_public_key = group_private_key.get_public_key();
+        let config = test_config(group_public_key);
+
+        let forged_private_key = crypto::generate_random_private_key();
+        let forged_public_key = forged_private_key.get_public_key();
+        let forged_part = signed_part(0, 1, vec![9, 9, 9], &forged_private_key, forged_public_key);
+
+        let mut transfer = ConsensusStateTransfer::new(BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION);
+        assert!(transfer.receive_part(&forged_part, &config).is_err());
+        assert!(!transfer.is_complete());
+    }
+
+    #[test]
+    fn test_transfer_rejects_inconsistent_total_parts() {
+        let group_private_key = crypto::generate_random_private_key();
+        let group_public_key = group_private_key.get_public_key();
+        let config = test_config(group_public_key.clone());
+
+        let mut transfer = ConsensusStateTransfer::new(BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION);
+        let part0 = signed_part(0, 2, vec![1], &group_private_key, group_public_key.clone());
+        transfer.receive_part(&part0, &config).unwrap();
+
+        let part1 = signed_part(1, 3, vec![2], &group_private_key, group_public_key);
+        assert!(transfer.receive_part(&part1, &config).is_err());
+    }
+
+    #[test]
+    fn test_transfer_rejects_out_of_order_parts() {
+        let group_private_key = crypto::generate_random_private_key();
+        let group_public_key = group_private_key.get_public_key();
+        let config = test_config(group_public_key.clone());
+
+        let mut transfer = ConsensusStateTransfer::new(BOOTSTRAP_THRESHOLD_AUTH_MIN_VERSION);
+
+        // part 1 arriving before part 0 must be rejected, not filled in out of order
+        let part1 = signed_part(1, 2, vec![4, 5], &group_private_key, group_public_key.clone());
+        assert!(transfer.receive_part(&part1, &config).is_err());
+        assert_eq!(transfer.next_cursor(), 0);
+
+        let part0 = signed_part(0, 2, vec![1, 2, 3], &group_private_key, group_public_key.clone());
+        transfer.receive_part(&part0, &config).unwrap();
+
+        // a duplicate of the part just accepted must also be rejected
+        assert!(transfer.receive_part(&part0, &config).is_err());
+
+        transfer.receive_part(&part1, &config).unwrap();
+        assert!(transfer.is_complete());
+    }
+
+    #[test]
+    fn test_transfer_verifies_single_auth_part() {
+        let server_private_key = crypto::generate_random_private_key();
+        let server_public_key = server_private_key.get_public_key();
+
+        let unrelated_public_key = crypto::generate_random_private_key().get_public_key();
+        let config = BootstrapConfig::new(server_public_key.clone(), unrelated_public_key, 1, 1)
+            .unwrap();
+
+        let data = vec![7, 8, 9];
+        let signature = server_private_key.sign(&Hash::hash(&data)).unwrap();
+        let part = BootstrapMessage::ConsensusStatePart {
+            part_index: 0,
+            total_parts: 1,
+            data,
+            auth: BootstrapAuth::Single(signature),
+        };
+
+        let mut transfer = ConsensusStateTransfer::new(1);
+        transfer.receive_part(&part, &config).unwrap();
+        assert!(transfer.is_complete());
+
+        // a part "signed" by an unrelated key must not verify
+        let other_private_key = crypto::generate_random_private_key();
+        let forged_data = vec![1];
+        let forged_signature = other_private_key.sign(&Hash::hash(&forged_data)).unwrap();
+        let forged_part = BootstrapMessage::ConsensusStatePart {
+            part_index: 0,
+            total_parts: 1,
+            data: forged_data,
+            auth: BootstrapAuth::Single(forged_signature),
+        };
+        let mut forged_transfer = ConsensusStateTransfer::new(1);
+        assert!(forged_transfer.receive_part(&forged_part, &config).is_err());
+    }
+}